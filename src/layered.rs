@@ -0,0 +1,105 @@
+use crate::toml_include::merge_toml_values;
+use crate::{ConfigMetaSpec, IncludeLoadError, load_toml_with_includes, resolve_config_path_strict};
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+/// Default system-wide config directory, lowest precedence.
+pub const SYSTEM_CONFIG_DIR: &str = "/etc/hypr";
+
+/// Resolve the user config directory, honoring `$XDG_CONFIG_HOME` (if given)
+/// and falling back to `~/.config/hypr` otherwise.
+///
+/// Takes `xdg_config_home` explicitly rather than reading the environment
+/// itself, so callers (and tests) control it directly; see
+/// [`user_config_dir_from_env`] for the convenience wrapper that reads
+/// `$XDG_CONFIG_HOME`.
+#[must_use]
+pub fn user_config_dir(home_dir: &Path, xdg_config_home: Option<&Path>) -> PathBuf {
+    match xdg_config_home {
+        Some(xdg) if !xdg.as_os_str().is_empty() => xdg.join("hypr"),
+        _ => home_dir.join(".config").join("hypr"),
+    }
+}
+
+/// [`user_config_dir`], reading `$XDG_CONFIG_HOME` from the process
+/// environment.
+#[must_use]
+pub fn user_config_dir_from_env(home_dir: &Path) -> PathBuf {
+    let xdg_config_home = std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from);
+    user_config_dir(home_dir, xdg_config_home.as_deref())
+}
+
+/// Load a config file from standard-location layering roots, merging in
+/// precedence order (lowest first): `system_dir`, `user_dir`, then an
+/// optional caller-supplied `local_override` root.
+///
+/// Under each root, `filename` is resolved with [`resolve_config_path_strict`]
+/// against `spec`, recursively include-merged with [`load_toml_with_includes`],
+/// and merged on top of lower layers: higher layers overwrite scalars, while
+/// table values merge by key.
+///
+/// Returns the merged value plus the ordered list of layer paths that
+/// actually contributed, so callers can tell users which files were used.
+///
+/// `system_dir` and `user_dir` are taken explicitly (rather than defaulted
+/// to [`SYSTEM_CONFIG_DIR`]/[`user_config_dir_from_env`] internally) so every
+/// layer is independently testable; see [`load_layered_config_default`] for
+/// the convenience wrapper that fills in the real standard locations.
+pub fn load_layered_config(
+    spec: &ConfigMetaSpec<'_>,
+    filename: &str,
+    include_key: &str,
+    home_dir: &Path,
+    system_dir: &Path,
+    user_dir: &Path,
+    local_override: Option<&Path>,
+) -> Result<(Value, Vec<PathBuf>), IncludeLoadError> {
+    let roots = [system_dir, user_dir];
+
+    let mut merged: Option<Value> = None;
+    let mut layers = Vec::new();
+
+    for root in roots.into_iter().chain(local_override) {
+        let fallback = root.join(filename);
+        let Some(resolved) = resolve_config_path_strict(root, &fallback, spec) else {
+            continue;
+        };
+
+        let layer_value = load_toml_with_includes(&resolved, include_key, home_dir)?;
+        layers.push(resolved);
+
+        merged = Some(match merged {
+            Some(mut base) => {
+                merge_toml_values(&mut base, layer_value);
+                base
+            }
+            None => layer_value,
+        });
+    }
+
+    let value = merged.unwrap_or_else(|| Value::Table(toml::map::Map::new()));
+    Ok((value, layers))
+}
+
+/// [`load_layered_config`] using the real standard locations:
+/// [`SYSTEM_CONFIG_DIR`] for the system layer and
+/// [`user_config_dir_from_env`] for the user layer.
+pub fn load_layered_config_default(
+    spec: &ConfigMetaSpec<'_>,
+    filename: &str,
+    include_key: &str,
+    home_dir: &Path,
+    local_override: Option<&Path>,
+) -> Result<(Value, Vec<PathBuf>), IncludeLoadError> {
+    let system_dir = PathBuf::from(SYSTEM_CONFIG_DIR);
+    let user_dir = user_config_dir_from_env(home_dir);
+    load_layered_config(
+        spec,
+        filename,
+        include_key,
+        home_dir,
+        &system_dir,
+        &user_dir,
+        local_override,
+    )
+}
@@ -3,6 +3,24 @@
 //! `hypr-conf` lets tools identify config files by a simple human-readable
 //! header instead of hard-coded filenames.
 
+mod layered;
+mod source;
+mod toml_include;
+
+pub use layered::{
+    SYSTEM_CONFIG_DIR, load_layered_config, load_layered_config_default, user_config_dir,
+    user_config_dir_from_env,
+};
+pub use source::{
+    SourceGraph, collect_source_graph, collect_source_graph_detailed,
+    collect_source_graph_excluding, expand_source_expression_to_path, extract_sources,
+    has_glob_chars, parse_source_value, resolve_source_targets, source_expression_matches_path,
+};
+pub use toml_include::{
+    IncludeGraph, IncludeLoadError, collect_include_graph, load_toml_with_includes,
+    load_toml_with_includes_excluding, load_toml_with_includes_layered,
+};
+
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -150,6 +168,123 @@ pub fn discover_config_files(root: &Path, spec: &ConfigMetaSpec<'_>) -> Vec<Path
     matches
 }
 
+/// Split an include-style glob pattern into its longest literal directory
+/// prefix and the remaining wildcard tail.
+///
+/// e.g. `themes/**/*.conf` splits into base `themes` and tail `**/*.conf`.
+/// A pattern with no wildcard components splits into itself and an empty
+/// tail.
+#[must_use]
+pub fn split_glob_base(pattern: &Path) -> (PathBuf, String) {
+    let mut base = PathBuf::new();
+    let mut tail_components = Vec::new();
+    let mut in_tail = false;
+
+    for component in pattern.components() {
+        let component_str = component.as_os_str().to_string_lossy();
+        if !in_tail && !has_glob_chars(&component_str) {
+            base.push(component.as_os_str());
+        } else {
+            in_tail = true;
+            tail_components.push(component_str.into_owned());
+        }
+    }
+
+    (base, tail_components.join("/"))
+}
+
+/// Discover config files matching include-style glob patterns without
+/// scanning directories outside each pattern's literal base.
+///
+/// Each pattern is expanded the same way `source`/`include` values are
+/// (`~`, `$HOME`, relative to `base_dir`), then split with
+/// [`split_glob_base`] so the walk only starts at that pattern's literal
+/// base directory instead of all of `base_dir`. Unless the tail contains a
+/// recursive `**` component, the walk is also capped at the tail's
+/// component count, so e.g. `themes/*.conf` never descends into
+/// subdirectories of `themes` at all. Each visited file is matched against
+/// the full expanded pattern with [`glob::Pattern::matches_path`] and, same
+/// as [`discover_config_files`], against `spec`.
+///
+/// Returned paths are deduplicated and sorted for deterministic behavior.
+#[must_use]
+pub fn discover_config_files_matching(
+    base_dir: &Path,
+    home_dir: &Path,
+    patterns: &[String],
+    spec: &ConfigMetaSpec<'_>,
+) -> Vec<PathBuf> {
+    let mut matches = Vec::new();
+
+    for pattern in patterns {
+        let expanded = expand_source_expression_to_path(pattern, base_dir, home_dir);
+        let (scoped_root, tail) = split_glob_base(&expanded);
+
+        if tail.is_empty() {
+            // No wildcard component: `scoped_root` is the literal file
+            // itself, not a directory to walk.
+            if file_matches(&scoped_root, spec) {
+                matches.push(scoped_root);
+            }
+            continue;
+        }
+
+        let Ok(glob_pattern) = glob::Pattern::new(&expanded.to_string_lossy()) else {
+            continue;
+        };
+
+        // Without a recursive `**` component, matches can only live exactly
+        // `tail`'s component count below `scoped_root`, so cap the walk
+        // there instead of descending into every nested subdirectory.
+        let max_depth = if tail.split('/').any(|component| component == "**") {
+            None
+        } else {
+            Some(tail.split('/').count())
+        };
+
+        walk_scoped_matches(&scoped_root, &glob_pattern, max_depth, spec, &mut matches);
+    }
+
+    matches.sort();
+    matches.dedup();
+    matches
+}
+
+fn walk_scoped_matches(
+    root: &Path,
+    pattern: &glob::Pattern,
+    max_depth: Option<usize>,
+    spec: &ConfigMetaSpec<'_>,
+    matches: &mut Vec<PathBuf>,
+) {
+    let mut stack = vec![(root.to_path_buf(), 0usize)];
+
+    while let Some((dir, depth)) = stack.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                let can_descend = match max_depth {
+                    Some(max) => depth + 1 < max,
+                    None => true,
+                };
+                if can_descend {
+                    stack.push((path, depth + 1));
+                }
+                continue;
+            }
+
+            if pattern.matches_path(&path) && file_matches(&path, spec) {
+                matches.push(path);
+            }
+        }
+    }
+}
+
 /// Resolve config path using metadata discovery with deterministic fallback.
 ///
 /// Resolution order:
@@ -126,6 +126,22 @@ pub fn source_expression_matches_path(
 /// Cycles are handled by deduplication; each file appears at most once.
 #[must_use]
 pub fn collect_source_graph(root: &Path, home_dir: &Path) -> Vec<PathBuf> {
+    collect_source_graph_excluding(root, home_dir, &[])
+}
+
+/// Collect a recursive `source = ...` graph, skipping any resolved source
+/// path that matches one of `excludes`.
+///
+/// `excludes` are glob patterns expanded the same way `source` values are
+/// (`~`, `$HOME`), then matched against each resolved path with
+/// [`glob::Pattern::matches_path`] *while traversing* — the excludes
+/// themselves are never passed to [`glob::glob`].
+#[must_use]
+pub fn collect_source_graph_excluding(
+    root: &Path,
+    home_dir: &Path,
+    excludes: &[String],
+) -> Vec<PathBuf> {
     let mut out = Vec::new();
     let mut stack = vec![root.to_path_buf()];
     let mut seen = HashSet::new();
@@ -147,9 +163,17 @@ pub fn collect_source_graph(root: &Path, home_dir: &Path) -> Vec<PathBuf> {
         for line in content.lines() {
             if let Some(source_value) = parse_source_value(line) {
                 for resolved in resolve_source_targets(source_value, base_dir, home_dir) {
-                    if resolved.exists() && resolved.is_file() {
-                        stack.push(resolved);
+                    if !resolved.exists() || !resolved.is_file() {
+                        continue;
                     }
+
+                    if excludes.iter().any(|pattern| {
+                        source_expression_matches_path(pattern, base_dir, home_dir, &resolved)
+                    }) {
+                        continue;
+                    }
+
+                    stack.push(resolved);
                 }
             }
         }
@@ -158,6 +182,96 @@ pub fn collect_source_graph(root: &Path, home_dir: &Path) -> Vec<PathBuf> {
     out
 }
 
+/// A `source = ...` dependency graph: which files exist (`nodes`) and which
+/// file pulled in which other file (`edges`, parent → child).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SourceGraph {
+    pub nodes: Vec<PathBuf>,
+    pub edges: Vec<(PathBuf, PathBuf)>,
+}
+
+/// Collect the recursive `source = ...` dependency graph starting from
+/// `root`, alongside any cycles found.
+///
+/// Unlike [`collect_source_graph`], edges are preserved rather than
+/// collapsed into a flat, deduplicated list, so tooling can render the real
+/// dependency graph. Nodes are still visited at most once (cycle-safe
+/// dedup), but a child that resolves back to one of its own ancestors is
+/// additionally reported as a cycle chain instead of silently stopping the
+/// walk, one `Vec<PathBuf>` per cycle found (ancestor chain, root-first,
+/// followed by the path that closes the loop).
+#[must_use]
+pub fn collect_source_graph_detailed(root: &Path, home_dir: &Path) -> (SourceGraph, Vec<Vec<PathBuf>>) {
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    let mut cycles = Vec::new();
+    let mut seen = HashSet::new();
+    let mut ancestors = Vec::new();
+
+    let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    seen.insert(canonical_root);
+    nodes.push(root.to_path_buf());
+
+    visit_source_children(
+        root,
+        home_dir,
+        &mut nodes,
+        &mut edges,
+        &mut cycles,
+        &mut seen,
+        &mut ancestors,
+    );
+
+    (SourceGraph { nodes, edges }, cycles)
+}
+
+fn visit_source_children(
+    file: &Path,
+    home_dir: &Path,
+    nodes: &mut Vec<PathBuf>,
+    edges: &mut Vec<(PathBuf, PathBuf)>,
+    cycles: &mut Vec<Vec<PathBuf>>,
+    seen: &mut HashSet<PathBuf>,
+    ancestors: &mut Vec<PathBuf>,
+) {
+    let canonical = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    ancestors.push(canonical);
+
+    let content = fs::read_to_string(file).unwrap_or_default();
+    let base_dir = file.parent().unwrap_or_else(|| Path::new("/"));
+
+    for line in content.lines() {
+        let Some(source_value) = parse_source_value(line) else {
+            continue;
+        };
+
+        for resolved in resolve_source_targets(source_value, base_dir, home_dir) {
+            if !resolved.exists() || !resolved.is_file() {
+                continue;
+            }
+
+            edges.push((file.to_path_buf(), resolved.clone()));
+
+            let resolved_canonical = resolved.canonicalize().unwrap_or_else(|_| resolved.clone());
+            if let Some(start) = ancestors.iter().position(|a| *a == resolved_canonical) {
+                let mut chain = ancestors[start..].to_vec();
+                chain.push(resolved_canonical);
+                cycles.push(chain);
+                continue;
+            }
+
+            if !seen.insert(resolved_canonical) {
+                continue;
+            }
+
+            nodes.push(resolved.clone());
+            visit_source_children(&resolved, home_dir, nodes, edges, cycles, seen, ancestors);
+        }
+    }
+
+    ancestors.pop();
+}
+
 fn strip_comment(line: &str) -> &str {
     line.split_once('#')
         .map(|(before, _)| before)
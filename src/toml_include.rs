@@ -1,5 +1,5 @@
-use crate::resolve_source_targets;
-use std::collections::HashSet;
+use crate::{resolve_source_targets, source_expression_matches_path};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -53,15 +53,60 @@ pub fn load_toml_with_includes(
     include_key: &str,
     home_dir: &Path,
 ) -> Result<Value, IncludeLoadError> {
+    load_toml_with_includes_excluding(path, include_key, home_dir, &[])
+}
+
+/// Load TOML and recursively resolve top-level include patterns, skipping any
+/// resolved include path that matches one of `excludes`.
+///
+/// `excludes` are glob patterns expanded the same way include/source values
+/// are (`~`, `$HOME`), then matched against each resolved include path with
+/// [`glob::Pattern::matches_path`] *while traversing* — the excludes
+/// themselves are never passed to [`glob::glob`].
+pub fn load_toml_with_includes_excluding(
+    path: &Path,
+    include_key: &str,
+    home_dir: &Path,
+    excludes: &[String],
+) -> Result<Value, IncludeLoadError> {
+    let mut stack = HashSet::new();
+    load_toml_with_includes_inner(path, include_key, home_dir, excludes, &mut stack, None)
+}
+
+/// Load TOML and recursively resolve top-level include patterns, additionally
+/// tracking which file last set each dotted key path (e.g. `style.bg`).
+///
+/// The returned map records, for every scalar leaf value in the merged
+/// result, the [`PathBuf`] of the last file (root or include) that set it —
+/// useful for tools that want to show users exactly which include file won
+/// for a given setting. Resolved include paths matching one of `excludes`
+/// are skipped the same way [`load_toml_with_includes_excluding`] skips them.
+pub fn load_toml_with_includes_layered(
+    path: &Path,
+    include_key: &str,
+    home_dir: &Path,
+    excludes: &[String],
+) -> Result<(Value, HashMap<String, PathBuf>), IncludeLoadError> {
     let mut stack = HashSet::new();
-    load_toml_with_includes_inner(path, include_key, home_dir, &mut stack)
+    let mut provenance = HashMap::new();
+    let value = load_toml_with_includes_inner(
+        path,
+        include_key,
+        home_dir,
+        excludes,
+        &mut stack,
+        Some(&mut provenance),
+    )?;
+    Ok((value, provenance))
 }
 
 fn load_toml_with_includes_inner(
     path: &Path,
     include_key: &str,
     home_dir: &Path,
+    excludes: &[String],
     stack: &mut HashSet<PathBuf>,
+    mut provenance: Option<&mut HashMap<String, PathBuf>>,
 ) -> Result<Value, IncludeLoadError> {
     let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
     if !stack.insert(canonical.clone()) {
@@ -71,6 +116,9 @@ fn load_toml_with_includes_inner(
     let result = (|| -> Result<Value, IncludeLoadError> {
         let content = fs::read_to_string(path)?;
         let mut root_value: Value = toml::from_str(&content)?;
+        if let Some(provenance) = provenance.as_deref_mut() {
+            record_provenance(&root_value, path, "", provenance);
+        }
 
         let include_patterns = extract_include_patterns(&root_value, include_key);
         let base_dir = path.parent().unwrap_or_else(|| Path::new("/"));
@@ -81,9 +129,25 @@ fn load_toml_with_includes_inner(
                     continue;
                 }
 
-                let included =
-                    load_toml_with_includes_inner(&include_path, include_key, home_dir, stack)?;
-                merge_toml_values(&mut root_value, included);
+                if is_excluded(&include_path, excludes, base_dir, home_dir) {
+                    continue;
+                }
+
+                let included = load_toml_with_includes_inner(
+                    &include_path,
+                    include_key,
+                    home_dir,
+                    excludes,
+                    stack,
+                    provenance.as_deref_mut(),
+                )?;
+
+                match provenance.as_deref_mut() {
+                    Some(provenance) => {
+                        merge_toml_values_tracked(&mut root_value, included, "", provenance)
+                    }
+                    None => merge_toml_values(&mut root_value, included),
+                }
             }
         }
 
@@ -94,6 +158,104 @@ fn load_toml_with_includes_inner(
     result
 }
 
+/// Record the source file for every scalar leaf under `value`, keyed by its
+/// dotted path from the document root.
+///
+/// Called once per file in include order, so a later call for the same key
+/// (from this file's own content, or from a nested include processed after
+/// it) naturally overwrites an earlier one — matching the "later overwrites
+/// earlier" semantics of [`merge_toml_values`].
+fn record_provenance(
+    value: &Value,
+    source: &Path,
+    prefix: &str,
+    provenance: &mut HashMap<String, PathBuf>,
+) {
+    let mut paths = Vec::new();
+    collect_leaf_paths(value, prefix, &mut paths);
+    for path in paths {
+        provenance.insert(path, source.to_path_buf());
+    }
+}
+
+/// Collect the dotted path of every scalar leaf reachable from `value`.
+fn collect_leaf_paths(value: &Value, prefix: &str, out: &mut Vec<String>) {
+    match value {
+        Value::Table(map) => {
+            for (key, value) in map {
+                collect_leaf_paths(value, &join_key_path(prefix, key), out);
+            }
+        }
+        _ => out.push(prefix.to_string()),
+    }
+}
+
+/// [`merge_toml_values`], additionally keeping `provenance` in sync with the
+/// merged result.
+///
+/// Merging an already-loaded `other` into `base` never needs to *add*
+/// provenance entries here — every leaf inside `other` was already tagged to
+/// its owning file while `other` itself was built (via [`record_provenance`]
+/// or a nested call to this function). What it does need to do is *remove*
+/// stale entries: when a key's value changes TOML variant across layers
+/// (e.g. a scalar `style.bg` in one file becomes a table `style.bg = { r,
+/// g }` in a later include, or vice versa), the leaf paths that existed
+/// under `prefix` in the old `base` value disappear from the merged result,
+/// so any surviving provenance entry for them is stale and must be purged —
+/// but only the ones that aren't *also* live leaf paths of `other` (which
+/// are already correctly tagged to whichever file produced them).
+fn merge_toml_values_tracked(
+    base: &mut Value,
+    other: Value,
+    prefix: &str,
+    provenance: &mut HashMap<String, PathBuf>,
+) {
+    match (base, other) {
+        (Value::Table(base_map), Value::Table(other_map)) => {
+            for (key, value) in other_map {
+                let key_path = join_key_path(prefix, &key);
+                match base_map.get_mut(&key) {
+                    Some(base_value) => {
+                        merge_toml_values_tracked(base_value, value, &key_path, provenance)
+                    }
+                    None => {
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base_value, other_value) => {
+            let mut old_leaves = Vec::new();
+            collect_leaf_paths(base_value, prefix, &mut old_leaves);
+            let mut new_leaves = Vec::new();
+            collect_leaf_paths(&other_value, prefix, &mut new_leaves);
+            let new_leaves: HashSet<String> = new_leaves.into_iter().collect();
+
+            for old_leaf in old_leaves {
+                if !new_leaves.contains(&old_leaf) {
+                    provenance.remove(&old_leaf);
+                }
+            }
+
+            *base_value = other_value;
+        }
+    }
+}
+
+fn join_key_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+fn is_excluded(target: &Path, excludes: &[String], base_dir: &Path, home_dir: &Path) -> bool {
+    excludes
+        .iter()
+        .any(|pattern| source_expression_matches_path(pattern, base_dir, home_dir, target))
+}
+
 fn extract_include_patterns(root: &Value, include_key: &str) -> Vec<String> {
     let mut include_patterns = Vec::new();
     if let Some(includes) = root.get(include_key).and_then(Value::as_array) {
@@ -106,7 +268,7 @@ fn extract_include_patterns(root: &Value, include_key: &str) -> Vec<String> {
     include_patterns
 }
 
-fn merge_toml_values(base: &mut Value, other: Value) {
+pub(crate) fn merge_toml_values(base: &mut Value, other: Value) {
     match (base, other) {
         (Value::Table(base_map), Value::Table(other_map)) => {
             for (key, value) in other_map {
@@ -123,3 +285,108 @@ fn merge_toml_values(base: &mut Value, other: Value) {
         }
     }
 }
+
+/// An `include = [...]` dependency graph: which files exist (`nodes`) and
+/// which file pulled in which other file (`edges`, parent → child).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IncludeGraph {
+    pub nodes: Vec<PathBuf>,
+    pub edges: Vec<(PathBuf, PathBuf)>,
+}
+
+/// Collect the recursive `include = [...]` dependency graph starting from
+/// `root`, alongside any cycles found.
+///
+/// Mirrors [`crate::collect_source_graph_detailed`] for TOML include chains:
+/// unlike [`load_toml_with_includes`], this never errors out on a cycle — it
+/// keeps walking and records the offending chain instead, so `source`-style
+/// and `include`-style configs get the same inspection surface. Edges are
+/// preserved (parent → child) rather than collapsed into a merged value, and
+/// nodes are still visited at most once (cycle-safe dedup); a child that
+/// resolves back to one of its own ancestors is reported as a cycle chain,
+/// one `Vec<PathBuf>` per cycle found (ancestor chain, root-first, followed
+/// by the path that closes the loop).
+#[must_use]
+pub fn collect_include_graph(
+    root: &Path,
+    include_key: &str,
+    home_dir: &Path,
+) -> (IncludeGraph, Vec<Vec<PathBuf>>) {
+    let mut state = IncludeGraphWalk::default();
+
+    let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    state.seen.insert(canonical_root);
+    state.nodes.push(root.to_path_buf());
+
+    visit_include_children(root, include_key, home_dir, &mut state);
+
+    (
+        IncludeGraph {
+            nodes: state.nodes,
+            edges: state.edges,
+        },
+        state.cycles,
+    )
+}
+
+#[derive(Default)]
+struct IncludeGraphWalk {
+    nodes: Vec<PathBuf>,
+    edges: Vec<(PathBuf, PathBuf)>,
+    cycles: Vec<Vec<PathBuf>>,
+    seen: HashSet<PathBuf>,
+    ancestors: Vec<PathBuf>,
+}
+
+fn visit_include_children(
+    file: &Path,
+    include_key: &str,
+    home_dir: &Path,
+    state: &mut IncludeGraphWalk,
+) {
+    let canonical = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    state.ancestors.push(canonical);
+
+    let Ok(content) = fs::read_to_string(file) else {
+        state.ancestors.pop();
+        return;
+    };
+    let Ok(root_value) = toml::from_str::<Value>(&content) else {
+        state.ancestors.pop();
+        return;
+    };
+
+    let include_patterns = extract_include_patterns(&root_value, include_key);
+    let base_dir = file.parent().unwrap_or_else(|| Path::new("/"));
+
+    for pattern in include_patterns {
+        for resolved in resolve_source_targets(&pattern, base_dir, home_dir) {
+            if !resolved.exists() || !resolved.is_file() {
+                continue;
+            }
+
+            state.edges.push((file.to_path_buf(), resolved.clone()));
+
+            let resolved_canonical = resolved.canonicalize().unwrap_or_else(|_| resolved.clone());
+            if let Some(start) = state
+                .ancestors
+                .iter()
+                .position(|a| *a == resolved_canonical)
+            {
+                let mut chain = state.ancestors[start..].to_vec();
+                chain.push(resolved_canonical);
+                state.cycles.push(chain);
+                continue;
+            }
+
+            if !state.seen.insert(resolved_canonical) {
+                continue;
+            }
+
+            state.nodes.push(resolved.clone());
+            visit_include_children(&resolved, include_key, home_dir, state);
+        }
+    }
+
+    state.ancestors.pop();
+}
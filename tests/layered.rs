@@ -0,0 +1,139 @@
+use hypr_conf::{ConfigMetaSpec, load_layered_config, user_config_dir};
+use std::fs;
+use tempfile::tempdir;
+use toml::Value;
+
+#[test]
+fn local_override_layer_wins_and_is_reported() {
+    let dir = tempdir().expect("tempdir");
+    let home = dir.path().join("home");
+    let system = dir.path().join("system");
+    let user = dir.path().join("user");
+    let local = dir.path().join("local");
+    fs::create_dir_all(&home).expect("create home");
+    fs::create_dir_all(&system).expect("create system");
+    fs::create_dir_all(&user).expect("create user");
+    fs::create_dir_all(&local).expect("create local");
+
+    let system_config = system.join("hyprbar.conf");
+    fs::write(
+        &system_config,
+        "# hypr metadata\n# type = bar\n[layout]\nleft = 10\n",
+    )
+    .expect("write system config");
+
+    let config_path = local.join("hyprbar.conf");
+    fs::write(
+        &config_path,
+        "# hypr metadata\n# type = bar\n[layout]\nleft = 33\n",
+    )
+    .expect("write local config");
+
+    let spec = ConfigMetaSpec::for_type("bar", &["conf"]);
+    let (value, layers) = load_layered_config(
+        &spec,
+        "hyprbar.conf",
+        "include",
+        &home,
+        &system,
+        &user,
+        Some(local.as_path()),
+    )
+    .expect("load layered config");
+
+    let layout = value.get("layout").and_then(Value::as_table).expect("layout");
+    assert_eq!(layout.get("left").and_then(Value::as_integer), Some(33));
+    assert_eq!(layers, vec![system_config, config_path]);
+}
+
+#[test]
+fn higher_layers_overwrite_scalars_while_tables_merge_by_key() {
+    let dir = tempdir().expect("tempdir");
+    let home = dir.path().join("home");
+    let system = dir.path().join("system");
+    let user = dir.path().join("user");
+    let local = dir.path().join("local");
+    fs::create_dir_all(&home).expect("create home");
+    fs::create_dir_all(&system).expect("create system");
+    fs::create_dir_all(&user).expect("create user");
+    fs::create_dir_all(&local).expect("create local");
+
+    let system_config = system.join("hyprbar.conf");
+    let user_config = user.join("hyprbar.conf");
+    let local_config = local.join("hyprbar.conf");
+
+    fs::write(
+        &system_config,
+        "# hypr metadata\n# type = bar\n[layout]\nleft = 10\nright = 20\n",
+    )
+    .expect("write system config");
+    fs::write(
+        &user_config,
+        "# hypr metadata\n# type = bar\n[layout]\nleft = 33\n",
+    )
+    .expect("write user config");
+    fs::write(
+        &local_config,
+        "# hypr metadata\n# type = bar\n[style]\nbg = \"#111111\"\n",
+    )
+    .expect("write local config");
+
+    let spec = ConfigMetaSpec::for_type("bar", &["conf"]);
+    let (value, layers) = load_layered_config(
+        &spec,
+        "hyprbar.conf",
+        "include",
+        &home,
+        &system,
+        &user,
+        Some(local.as_path()),
+    )
+    .expect("load layered config");
+
+    let layout = value.get("layout").and_then(Value::as_table).expect("layout");
+    // `user` overwrites `system`'s `left` scalar...
+    assert_eq!(layout.get("left").and_then(Value::as_integer), Some(33));
+    // ...while `right` (only set by `system`) survives the merge.
+    assert_eq!(layout.get("right").and_then(Value::as_integer), Some(20));
+
+    // `local` only sets `style`, merged alongside `layout` by key rather
+    // than replacing it.
+    let style = value.get("style").and_then(Value::as_table).expect("style");
+    assert_eq!(style.get("bg").and_then(Value::as_str), Some("#111111"));
+
+    assert_eq!(layers, vec![system_config, user_config, local_config]);
+}
+
+#[test]
+fn no_matching_layers_returns_empty_value_and_layers() {
+    let dir = tempdir().expect("tempdir");
+    let home = dir.path().join("home");
+    let system = dir.path().join("system");
+    let user = dir.path().join("user");
+    fs::create_dir_all(&home).expect("create home");
+    fs::create_dir_all(&system).expect("create system");
+    fs::create_dir_all(&user).expect("create user");
+
+    let spec = ConfigMetaSpec::for_type("bar", &["conf"]);
+    let (value, layers) =
+        load_layered_config(&spec, "hyprbar.conf", "include", &home, &system, &user, None)
+            .expect("load layered config");
+
+    assert_eq!(value, Value::Table(toml::map::Map::new()));
+    assert!(layers.is_empty());
+}
+
+#[test]
+fn user_config_dir_prefers_explicit_xdg_override() {
+    let home = std::path::Path::new("/home/example");
+    let xdg = std::path::Path::new("/custom/xdg");
+
+    assert_eq!(
+        user_config_dir(home, Some(xdg)),
+        std::path::PathBuf::from("/custom/xdg/hypr")
+    );
+    assert_eq!(
+        user_config_dir(home, None),
+        std::path::PathBuf::from("/home/example/.config/hypr")
+    );
+}
@@ -1,7 +1,9 @@
 use hypr_conf::{
-    ConfigMetaSpec, TYPE_KEY, discover_config_files, parse_metadata_header, resolve_config_path,
+    ConfigMetaSpec, TYPE_KEY, discover_config_files, discover_config_files_matching,
+    parse_metadata_header, resolve_config_path, split_glob_base,
 };
 use std::fs;
+use std::path::{Path, PathBuf};
 use tempfile::tempdir;
 
 #[test]
@@ -36,6 +38,114 @@ fn discovers_renamed_config_by_metadata() {
     assert_eq!(found, vec![config_path]);
 }
 
+#[test]
+fn splits_glob_pattern_into_base_and_tail() {
+    let (base, tail) = split_glob_base(Path::new("themes/**/*.conf"));
+    assert_eq!(base, PathBuf::from("themes"));
+    assert_eq!(tail, "**/*.conf");
+
+    let (base, tail) = split_glob_base(Path::new("hyprbar.conf"));
+    assert_eq!(base, PathBuf::from("hyprbar.conf"));
+    assert_eq!(tail, "");
+}
+
+#[test]
+fn scoped_discovery_only_reads_matching_base_directories() {
+    let dir = tempdir().expect("tempdir");
+    let themes = dir.path().join("themes");
+    let other = dir.path().join("unrelated");
+    fs::create_dir_all(&themes).expect("create themes");
+    fs::create_dir_all(&other).expect("create other");
+
+    let theme_cfg = themes.join("dark.conf");
+    fs::write(
+        &theme_cfg,
+        "# hypr metadata\n# type = theme\n[theme]\nname = \"dark\"\n",
+    )
+    .expect("write theme config");
+
+    // A file matching the metadata spec but outside the pattern's base must
+    // never be returned, even though it would match plain metadata discovery.
+    let unrelated_cfg = other.join("also-theme.conf");
+    fs::write(
+        &unrelated_cfg,
+        "# hypr metadata\n# type = theme\n[theme]\nname = \"evil-twin\"\n",
+    )
+    .expect("write unrelated config");
+
+    let spec = ConfigMetaSpec::for_type("theme", &["conf"]);
+    let found = discover_config_files_matching(
+        dir.path(),
+        dir.path(),
+        &["themes/*.conf".to_string()],
+        &spec,
+    );
+
+    assert_eq!(found, vec![theme_cfg]);
+}
+
+#[test]
+fn scoped_discovery_caps_walk_depth_unless_pattern_is_recursive() {
+    let dir = tempdir().expect("tempdir");
+    let themes = dir.path().join("themes");
+    let nested = themes.join("sub");
+    fs::create_dir_all(&nested).expect("create nested themes dir");
+
+    let shallow_cfg = themes.join("dark.conf");
+    let deep_cfg = nested.join("deep.conf");
+    fs::write(
+        &shallow_cfg,
+        "# hypr metadata\n# type = theme\n[theme]\nname = \"dark\"\n",
+    )
+    .expect("write shallow config");
+    fs::write(
+        &deep_cfg,
+        "# hypr metadata\n# type = theme\n[theme]\nname = \"deep\"\n",
+    )
+    .expect("write deep config");
+
+    let spec = ConfigMetaSpec::for_type("theme", &["conf"]);
+
+    // A non-recursive tail (no `**`) must not descend into `themes/sub`.
+    let shallow_found = discover_config_files_matching(
+        dir.path(),
+        dir.path(),
+        &["themes/*.conf".to_string()],
+        &spec,
+    );
+    assert_eq!(shallow_found, vec![shallow_cfg.clone()]);
+
+    // A `**` tail is still expected to find the nested file.
+    let recursive_found = discover_config_files_matching(
+        dir.path(),
+        dir.path(),
+        &["themes/**/*.conf".to_string()],
+        &spec,
+    );
+    assert_eq!(recursive_found, vec![shallow_cfg, deep_cfg]);
+}
+
+#[test]
+fn scoped_discovery_handles_literal_non_glob_patterns() {
+    let dir = tempdir().expect("tempdir");
+    let config_path = dir.path().join("hyprbar.conf");
+    fs::write(
+        &config_path,
+        "# hypr metadata\n# type = bar\n[layout]\nleft = 33\n",
+    )
+    .expect("write config");
+
+    let spec = ConfigMetaSpec::for_type("bar", &["conf"]);
+    let found = discover_config_files_matching(
+        dir.path(),
+        dir.path(),
+        &["hyprbar.conf".to_string()],
+        &spec,
+    );
+
+    assert_eq!(found, vec![config_path]);
+}
+
 #[test]
 fn fallback_wins_when_present() {
     let dir = tempdir().expect("tempdir");
@@ -1,5 +1,6 @@
 use hypr_conf::{
-    collect_source_graph, expand_source_expression_to_path, extract_sources, parse_source_value,
+    collect_source_graph, collect_source_graph_detailed, collect_source_graph_excluding,
+    expand_source_expression_to_path, extract_sources, parse_source_value,
     resolve_source_targets,
 };
 use std::fs;
@@ -76,3 +77,60 @@ fn collects_source_graph_cycle_safe() {
     assert!(graph.contains(&b));
     assert!(graph.contains(&c));
 }
+
+#[test]
+fn collect_source_graph_skips_excluded_paths() {
+    let dir = tempdir().expect("tempdir");
+    let a = dir.path().join("a.conf");
+    let b = dir.path().join("b.conf");
+    let c = dir.path().join("c.conf");
+
+    fs::write(&a, r#"source = "b.conf""#).expect("write a");
+    fs::write(&b, "").expect("write b");
+    fs::write(&c, "").expect("write c");
+
+    let graph =
+        collect_source_graph_excluding(&a, dir.path(), &["b.conf".to_string()]);
+    assert_eq!(graph, vec![a]);
+    assert!(!graph.contains(&b));
+    assert!(!graph.contains(&c));
+}
+
+#[test]
+fn detailed_graph_preserves_edges() {
+    let dir = tempdir().expect("tempdir");
+    let a = dir.path().join("a.conf");
+    let b = dir.path().join("b.conf");
+    let c = dir.path().join("c.conf");
+
+    fs::write(&a, r#"source = "b.conf""#).expect("write a");
+    fs::write(&b, r#"source = "c.conf""#).expect("write b");
+    fs::write(&c, "").expect("write c");
+
+    let (graph, cycles) = collect_source_graph_detailed(&a, dir.path());
+
+    assert_eq!(graph.nodes, vec![a.clone(), b.clone(), c.clone()]);
+    assert_eq!(graph.edges, vec![(a, b.clone()), (b, c)]);
+    assert!(cycles.is_empty());
+}
+
+#[test]
+fn detailed_graph_reports_cycles_instead_of_hiding_them() {
+    let dir = tempdir().expect("tempdir");
+    let a = dir.path().join("a.conf");
+    let b = dir.path().join("b.conf");
+    let c = dir.path().join("c.conf");
+
+    fs::write(&a, r#"source = "b.conf""#).expect("write a");
+    fs::write(&b, r#"source = "c.conf""#).expect("write b");
+    fs::write(&c, r#"source = "a.conf""#).expect("write c");
+
+    let (graph, cycles) = collect_source_graph_detailed(&a, dir.path());
+
+    assert_eq!(graph.nodes.len(), 3);
+    assert_eq!(graph.edges.len(), 3);
+    assert_eq!(cycles.len(), 1);
+
+    let chain = &cycles[0];
+    assert_eq!(chain.first(), chain.last());
+}
@@ -1,4 +1,7 @@
-use hypr_conf::{IncludeLoadError, load_toml_with_includes};
+use hypr_conf::{
+    IncludeLoadError, collect_include_graph, load_toml_with_includes,
+    load_toml_with_includes_excluding, load_toml_with_includes_layered,
+};
 use std::fs;
 use tempfile::tempdir;
 use toml::Value;
@@ -58,6 +61,204 @@ strategy = "grid"
     assert_eq!(layout.get("strategy").and_then(Value::as_str), Some("grid"));
 }
 
+#[test]
+fn excluded_includes_are_never_loaded() {
+    let dir = tempdir().expect("tempdir");
+    let root = dir.path().join("root.conf");
+    let includes = dir.path().join("includes");
+    fs::create_dir_all(&includes).expect("create includes");
+
+    let enabled = includes.join("enabled.conf");
+    let disabled = includes.join("disabled.conf");
+
+    fs::write(
+        &root,
+        r##"
+include = ["includes/*.conf"]
+"##,
+    )
+    .expect("write root");
+    fs::write(
+        &enabled,
+        r##"
+[style]
+bg = "#111111"
+"##,
+    )
+    .expect("write enabled");
+    fs::write(
+        &disabled,
+        r##"
+[style]
+bg = "#ff0000"
+fg = "#ff0000"
+"##,
+    )
+    .expect("write disabled");
+
+    let loaded = load_toml_with_includes_excluding(
+        &root,
+        "include",
+        dir.path(),
+        &["includes/disabled.conf".to_string()],
+    )
+    .expect("load includes");
+
+    let style = loaded
+        .get("style")
+        .and_then(Value::as_table)
+        .expect("style");
+    assert_eq!(style.get("bg").and_then(Value::as_str), Some("#111111"));
+    // `fg` is only set by the excluded file.
+    assert_eq!(style.get("fg"), None);
+}
+
+#[test]
+fn layered_load_tracks_which_file_set_each_key() {
+    let dir = tempdir().expect("tempdir");
+    let root = dir.path().join("root.conf");
+    let includes = dir.path().join("includes");
+    fs::create_dir_all(&includes).expect("create includes");
+
+    let child = includes.join("child.conf");
+
+    fs::write(
+        &root,
+        r##"
+include = ["includes/*.conf"]
+[style]
+bg = "#111111"
+"##,
+    )
+    .expect("write root");
+
+    fs::write(
+        &child,
+        r##"
+[style]
+bg = "#222222"
+fg = "#ffffff"
+"##,
+    )
+    .expect("write child");
+
+    let (loaded, provenance) =
+        load_toml_with_includes_layered(&root, "include", dir.path(), &[]).expect("load includes");
+
+    let style = loaded
+        .get("style")
+        .and_then(Value::as_table)
+        .expect("style");
+    assert_eq!(style.get("bg").and_then(Value::as_str), Some("#222222"));
+
+    assert_eq!(provenance.get("style.bg"), Some(&child));
+    assert_eq!(provenance.get("style.fg"), Some(&child));
+}
+
+#[test]
+fn layered_load_combines_provenance_with_excludes() {
+    let dir = tempdir().expect("tempdir");
+    let root = dir.path().join("root.conf");
+    let includes = dir.path().join("includes");
+    fs::create_dir_all(&includes).expect("create includes");
+
+    let enabled = includes.join("enabled.conf");
+    let disabled = includes.join("disabled.conf");
+
+    fs::write(
+        &root,
+        r##"
+include = ["includes/*.conf"]
+[style]
+bg = "#111111"
+"##,
+    )
+    .expect("write root");
+    fs::write(
+        &enabled,
+        r##"
+[style]
+bg = "#222222"
+"##,
+    )
+    .expect("write enabled");
+    fs::write(
+        &disabled,
+        r##"
+[style]
+bg = "#ff0000"
+fg = "#ff0000"
+"##,
+    )
+    .expect("write disabled");
+
+    let (loaded, provenance) = load_toml_with_includes_layered(
+        &root,
+        "include",
+        dir.path(),
+        &["includes/disabled.conf".to_string()],
+    )
+    .expect("load includes");
+
+    let style = loaded
+        .get("style")
+        .and_then(Value::as_table)
+        .expect("style");
+    assert_eq!(style.get("bg").and_then(Value::as_str), Some("#222222"));
+    assert_eq!(provenance.get("style.bg"), Some(&enabled));
+    // `fg` is only set by the excluded file, so it has no value or source.
+    assert_eq!(style.get("fg"), None);
+    assert_eq!(provenance.get("style.fg"), None);
+}
+
+#[test]
+fn layered_load_clears_stale_provenance_when_a_value_changes_variant() {
+    let dir = tempdir().expect("tempdir");
+    let root = dir.path().join("root.conf");
+    let includes = dir.path().join("includes");
+    fs::create_dir_all(&includes).expect("create includes");
+
+    let child = includes.join("child.conf");
+
+    fs::write(
+        &root,
+        r##"
+include = ["includes/*.conf"]
+[style]
+bg = "flat"
+"##,
+    )
+    .expect("write root");
+
+    fs::write(
+        &child,
+        r##"
+[style.bg]
+r = 1
+g = 2
+"##,
+    )
+    .expect("write child");
+
+    let (loaded, provenance) =
+        load_toml_with_includes_layered(&root, "include", dir.path(), &[]).expect("load includes");
+
+    let bg = loaded
+        .get("style")
+        .and_then(Value::as_table)
+        .and_then(|style| style.get("bg"))
+        .and_then(Value::as_table)
+        .expect("style.bg table");
+    assert_eq!(bg.get("r").and_then(Value::as_integer), Some(1));
+    assert_eq!(bg.get("g").and_then(Value::as_integer), Some(2));
+
+    // `style.bg` is no longer a scalar, so the root's stale leaf entry must
+    // be gone rather than pointing at a value that no longer exists.
+    assert_eq!(provenance.get("style.bg"), None);
+    assert_eq!(provenance.get("style.bg.r"), Some(&child));
+    assert_eq!(provenance.get("style.bg.g"), Some(&child));
+}
+
 #[test]
 fn cycles_in_include_chain_return_error() {
     let dir = tempdir().expect("tempdir");
@@ -70,3 +271,40 @@ fn cycles_in_include_chain_return_error() {
     let err = load_toml_with_includes(&a, "include", dir.path()).expect_err("expected cycle");
     assert!(matches!(err, IncludeLoadError::CyclicInclude(_)));
 }
+
+#[test]
+fn include_graph_preserves_edges() {
+    let dir = tempdir().expect("tempdir");
+    let a = dir.path().join("a.conf");
+    let b = dir.path().join("b.conf");
+    let c = dir.path().join("c.conf");
+
+    fs::write(&a, r#"include = ["b.conf"]"#).expect("write a");
+    fs::write(&b, r#"include = ["c.conf"]"#).expect("write b");
+    fs::write(&c, "").expect("write c");
+
+    let (graph, cycles) = collect_include_graph(&a, "include", dir.path());
+
+    assert_eq!(graph.nodes, vec![a.clone(), b.clone(), c.clone()]);
+    assert_eq!(graph.edges, vec![(a, b.clone()), (b, c)]);
+    assert!(cycles.is_empty());
+}
+
+#[test]
+fn include_graph_reports_cycles_instead_of_erroring() {
+    let dir = tempdir().expect("tempdir");
+    let a = dir.path().join("a.conf");
+    let b = dir.path().join("b.conf");
+
+    fs::write(&a, r#"include = ["b.conf"]"#).expect("write a");
+    fs::write(&b, r#"include = ["a.conf"]"#).expect("write b");
+
+    let (graph, cycles) = collect_include_graph(&a, "include", dir.path());
+
+    assert_eq!(graph.nodes.len(), 2);
+    assert_eq!(graph.edges.len(), 2);
+    assert_eq!(cycles.len(), 1);
+
+    let chain = &cycles[0];
+    assert_eq!(chain.first(), chain.last());
+}